@@ -0,0 +1,155 @@
+// Tok Stream Library
+//
+//
+// MIT License
+//
+// Copyright (c) 2021, 2022 Systopia Lab, Computer Science, University of British Columbia
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Comment Kinds and Doc Comment Bodies
+//!
+//! [CommentKind] and [AttrStyle] give a comment token's shape and target a name, and
+//! [strip_doc_comment] turns its raw source text into the body a downstream tool (a doc
+//! generator, a linter) would actually want to read, with the comment markers and any block
+//! margin peeled off.
+
+/// The syntactic shape of a comment.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum CommentKind {
+    /// a `//`-style comment, terminated by the end of the line
+    Line,
+    /// a `/* */`-style comment, terminated by `*/`
+    Block,
+}
+
+/// Whether a documentation comment documents the item that follows it (`Outer`, e.g. `///`,
+/// `/** */`) or the item that contains it (`Inner`, e.g. `//!`, `/*! */`).
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum AttrStyle {
+    /// documents the following item, e.g. `/// ...` or `/** ... */`
+    Outer,
+    /// documents the enclosing item, e.g. `//! ...` or `/*! ... */`
+    Inner,
+}
+
+/// Strips the comment markers from `raw`, the full source text of a documentation comment
+/// (including its `///`/`//!`/`/** */`/`/*! */` markers), leaving just the documented body.
+///
+/// Line comments have their three-character marker and a single following space removed. Block
+/// comments have their opening and closing markers removed, and then the common leading
+/// indentation of the lines after the first is computed and removed from those lines, a single
+/// leading `*` and the space following it are dropped from every line, and a single leading
+/// and trailing blank line is trimmed from the result.
+pub fn strip_doc_comment(kind: CommentKind, raw: &str) -> String {
+    match kind {
+        CommentKind::Line => strip_line_marker(raw),
+        CommentKind::Block => strip_block_markers(raw),
+    }
+}
+
+fn strip_line_marker(raw: &str) -> String {
+    let body = raw.strip_prefix("///").or_else(|| raw.strip_prefix("//!")).unwrap_or(raw);
+    body.strip_prefix(' ').unwrap_or(body).to_string()
+}
+
+fn strip_block_markers(raw: &str) -> String {
+    let body = raw
+        .strip_prefix("/**")
+        .or_else(|| raw.strip_prefix("/*!"))
+        .unwrap_or(raw);
+    let body = body.strip_suffix("*/").unwrap_or(body);
+
+    let lines: Vec<&str> = body.lines().collect();
+    if lines.is_empty() {
+        return String::new();
+    }
+
+    let indent = lines
+        .iter()
+        .skip(1)
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.chars().count() - line.trim_start().chars().count())
+        .min()
+        .unwrap_or(0);
+
+    let mut out: Vec<String> = lines
+        .iter()
+        .enumerate()
+        .map(|(i, line)| {
+            let line = if i == 0 { line } else { skip_chars(line, indent) };
+            let line = line.strip_prefix('*').unwrap_or(line);
+            line.strip_prefix(' ').unwrap_or(line).to_string()
+        })
+        .collect();
+
+    if out.first().map(|l| l.trim().is_empty()).unwrap_or(false) {
+        out.remove(0);
+    }
+    if out.last().map(|l| l.trim().is_empty()).unwrap_or(false) {
+        out.pop();
+    }
+
+    out.join("\n")
+}
+
+/// Skips the first `n` *characters* of `line`, returning a valid substring even when those
+/// characters are multi-byte (e.g. an indent made of non-breaking spaces).
+fn skip_chars(line: &str, n: usize) -> &str {
+    match line.char_indices().nth(n) {
+        Some((idx, _)) => &line[idx..],
+        None => "",
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn strip_line_comment_drops_marker_and_leading_space() {
+        assert_eq!(strip_doc_comment(CommentKind::Line, "/// hello"), "hello");
+        assert_eq!(strip_doc_comment(CommentKind::Line, "//! hello"), "hello");
+    }
+
+    #[test]
+    fn strip_line_comment_with_no_leading_space() {
+        assert_eq!(strip_doc_comment(CommentKind::Line, "///hello"), "hello");
+    }
+
+    #[test]
+    fn strip_single_line_block_comment() {
+        assert_eq!(strip_doc_comment(CommentKind::Block, "/** hello */"), "hello ");
+    }
+
+    #[test]
+    fn strip_multiline_block_comment_drops_margin_and_blank_ends() {
+        let raw = "/**\n * line one\n * line two\n */";
+        assert_eq!(strip_doc_comment(CommentKind::Block, raw), "line one\nline two");
+    }
+
+    #[test]
+    fn strip_block_comment_with_nbsp_indent_does_not_panic() {
+        // Regression test: a non-breaking space (U+00A0, 2 bytes in UTF-8) in an interior
+        // line's indentation used to make the byte-offset slice land mid-character.
+        let raw = "/**\n\u{a0}\u{a0}* normal\n * nbsp\n */";
+        let stripped = strip_doc_comment(CommentKind::Block, raw);
+        assert!(stripped.contains("nbsp"));
+    }
+}
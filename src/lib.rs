@@ -0,0 +1,42 @@
+// Tok Stream Library
+//
+//
+// MIT License
+//
+// Copyright (c) 2021, 2022 Systopia Lab, Computer Science, University of British Columbia
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # tokstream
+//!
+//! A small, generic library for representing lexical tokens and grouping them
+//! into delimiter-balanced token trees, modeled after rustc's own token and
+//! tokenstream data structures.
+
+pub mod comment;
+pub mod lit;
+pub mod stream;
+pub mod tok;
+pub mod tree;
+
+pub use comment::{strip_doc_comment, AttrStyle, CommentKind};
+pub use lit::{unescape, unescape_bytes, Lit, LitKind, LitValue, UnescapeError, UnescapeErrorKind};
+pub use stream::{Mark, TokStream};
+pub use tok::{glue_stream, Tok, TokKind};
+pub use tree::{Delimiter, DelimSpan, Group, GroupingError, TokenStream, TokenTree};
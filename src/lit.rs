@@ -0,0 +1,434 @@
+// Tok Stream Library
+//
+//
+// MIT License
+//
+// Copyright (c) 2021, 2022 Systopia Lab, Computer Science, University of British Columbia
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Literal Values
+//!
+//! A [Lit] is a literal token broken down into the pieces a parser actually cares about: what
+//! kind of value it denotes, its raw symbol text, and an optional suffix. [Lit::unescape] goes
+//! one step further and decodes backslash escapes in that symbol text into the value it
+//! represents, so callers never have to hand-roll escape handling themselves.
+
+use srcspan::SrcLoc;
+
+/// The kind of value denoted by a [Lit].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum LitKind {
+    /// a single-quoted character literal, e.g. `'a'`
+    Char,
+    /// a byte literal, e.g. `b'a'`
+    Byte,
+    /// a string literal, e.g. `"hello"`
+    Str,
+    /// a byte string literal, e.g. `b"hello"`
+    ByteStr,
+    /// an integer literal, e.g. `42`
+    Integer,
+    /// a floating point literal, e.g. `4.2`
+    Float,
+    /// a boolean literal, `true` or `false`
+    Bool,
+}
+
+/// A decomposed literal token.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Lit {
+    /// the kind of value this literal denotes
+    pub kind: LitKind,
+    /// the literal's raw text, not including quotes, prefixes or suffix
+    pub symbol: String,
+    /// the literal's suffix, if any, e.g. `u32` in `1u32`
+    pub suffix: Option<String>,
+    /// whether this is a raw literal (`r"..."`, `br"..."`), which skips unescaping
+    pub raw: bool,
+}
+
+impl Lit {
+    /// Creates a new [Lit]
+    pub fn new(kind: LitKind, symbol: String, suffix: Option<String>, raw: bool) -> Self {
+        Lit { kind, symbol, suffix, raw }
+    }
+
+    /// Decodes this literal's symbol into its unescaped value.
+    ///
+    /// Raw literals skip unescaping entirely and are returned as-is. `Byte` and `ByteStr`
+    /// literals are decoded as raw bytes rather than `char`s, since a `\xNN` escape in a byte
+    /// literal denotes a byte in `0x00..=0xFF` that need not be valid UTF-8 on its own.
+    pub fn unescape(&self) -> Result<LitValue, UnescapeError> {
+        match self.kind {
+            LitKind::Byte | LitKind::ByteStr => {
+                let bytes = if self.raw {
+                    self.symbol.as_bytes().to_vec()
+                } else {
+                    unescape_bytes(&self.symbol)?
+                };
+                Ok(LitValue::Bytes(bytes))
+            }
+            _ => {
+                let text = if self.raw { self.symbol.clone() } else { unescape(&self.symbol)? };
+                Ok(LitValue::Str(text))
+            }
+        }
+    }
+}
+
+/// The decoded value of a [Lit], distinguishing text literals from byte literals so the latter
+/// are never forced through a `String` (and so potentially re-encoded as multi-byte UTF-8).
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum LitValue {
+    /// the decoded value of a `Char`, `Str`, `Integer`, `Float` or `Bool` literal
+    Str(String),
+    /// the decoded value of a `Byte` or `ByteStr` literal
+    Bytes(Vec<u8>),
+}
+
+/// An error encountered while unescaping a literal's symbol text.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct UnescapeError {
+    /// what went wrong
+    pub kind: UnescapeErrorKind,
+    /// the offset of the faulty escape within the literal's symbol text
+    pub loc: SrcLoc,
+}
+
+/// The specific way in which unescaping a literal failed.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum UnescapeErrorKind {
+    /// a `\` was not followed by a recognized escape character
+    UnknownEscape(char),
+    /// a `\` was the last character of the literal, with nothing to escape
+    LoneBackslash,
+    /// a `\xNN` escape was not followed by exactly two hex digits
+    InvalidHexEscape,
+    /// a `\u` escape was not followed by `{`, or its braces were never closed
+    UnterminatedUnicodeEscape,
+    /// a `\u{...}` escape did not contain a valid hex number
+    InvalidUnicodeEscape,
+    /// a `\u{...}` escape denoted a codepoint above `0x10FFFF` or in the surrogate range
+    InvalidCodepoint(u32),
+    /// a `\u{...}` escape appeared in a byte literal, which only allows `\xNN` byte escapes
+    UnicodeEscapeInByteLiteral,
+}
+
+/// Decodes the escapes in `symbol`, the raw contents of a non-raw string or char literal.
+///
+/// Processes `symbol` left to right. On each `\` the following character selects the escape:
+/// the standard single-character escapes (`\n`, `\t`, `\r`, `\\`, `\'`, `\"`, `\0`), `\xNN` for
+/// a two-hex-digit byte, and `\u{...}` for a braced hex Unicode scalar value (rejecting values
+/// above `0x10FFFF` or in the surrogate range `0xD800..=0xDFFF`). A `\` followed by a newline
+/// and any further leading whitespace is consumed as a line continuation and produces no
+/// output. All other characters are copied through unchanged. Every failure is tagged with the
+/// [SrcLoc] offset of the offending escape within `symbol`.
+pub fn unescape(symbol: &str) -> Result<String, UnescapeError> {
+    let chars: Vec<char> = symbol.chars().collect();
+    let mut out = String::with_capacity(symbol.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c != '\\' {
+            out.push(c);
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        let escape = chars.get(i).copied().ok_or(UnescapeError {
+            kind: UnescapeErrorKind::LoneBackslash,
+            loc: SrcLoc::from(start),
+        })?;
+
+        match escape {
+            'n' => {
+                out.push('\n');
+                i += 1;
+            }
+            't' => {
+                out.push('\t');
+                i += 1;
+            }
+            'r' => {
+                out.push('\r');
+                i += 1;
+            }
+            '\\' => {
+                out.push('\\');
+                i += 1;
+            }
+            '\'' => {
+                out.push('\'');
+                i += 1;
+            }
+            '"' => {
+                out.push('"');
+                i += 1;
+            }
+            '0' => {
+                out.push('\0');
+                i += 1;
+            }
+            'x' => {
+                let digits: String = chars[i + 1..].iter().take(2).collect();
+                if digits.len() != 2 || !digits.chars().all(|d| d.is_ascii_hexdigit()) {
+                    return Err(UnescapeError {
+                        kind: UnescapeErrorKind::InvalidHexEscape,
+                        loc: SrcLoc::from(start),
+                    });
+                }
+                let byte = u8::from_str_radix(&digits, 16).unwrap();
+                out.push(byte as char);
+                i += 1 + digits.len();
+            }
+            'u' => {
+                if chars.get(i + 1) != Some(&'{') {
+                    return Err(UnescapeError {
+                        kind: UnescapeErrorKind::UnterminatedUnicodeEscape,
+                        loc: SrcLoc::from(start),
+                    });
+                }
+                let hex_start = i + 2;
+                let mut end = hex_start;
+                while end < chars.len() && chars[end] != '}' {
+                    end += 1;
+                }
+                if end == chars.len() {
+                    return Err(UnescapeError {
+                        kind: UnescapeErrorKind::UnterminatedUnicodeEscape,
+                        loc: SrcLoc::from(start),
+                    });
+                }
+                let hex: String = chars[hex_start..end].iter().collect();
+                let value = u32::from_str_radix(&hex, 16).map_err(|_| UnescapeError {
+                    kind: UnescapeErrorKind::InvalidUnicodeEscape,
+                    loc: SrcLoc::from(start),
+                })?;
+                if value > 0x10FFFF || (0xD800..=0xDFFF).contains(&value) {
+                    return Err(UnescapeError {
+                        kind: UnescapeErrorKind::InvalidCodepoint(value),
+                        loc: SrcLoc::from(start),
+                    });
+                }
+                let ch = char::from_u32(value).ok_or(UnescapeError {
+                    kind: UnescapeErrorKind::InvalidCodepoint(value),
+                    loc: SrcLoc::from(start),
+                })?;
+                out.push(ch);
+                i = end + 1;
+            }
+            '\n' => {
+                i += 1;
+                while chars.get(i).map(|c| c.is_whitespace()).unwrap_or(false) {
+                    i += 1;
+                }
+            }
+            other => {
+                return Err(UnescapeError {
+                    kind: UnescapeErrorKind::UnknownEscape(other),
+                    loc: SrcLoc::from(start),
+                });
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+/// Decodes the escapes in `symbol`, the raw contents of a non-raw byte or byte-string literal,
+/// into raw bytes rather than a `String`.
+///
+/// This mirrors [unescape], except `\xNN` pushes the literal byte value `0x00..=0xFF` instead
+/// of re-encoding it as a (possibly multi-byte) UTF-8 scalar, and `\u{...}` is rejected with
+/// [UnescapeErrorKind::UnicodeEscapeInByteLiteral] since byte literals have no notion of a
+/// Unicode scalar value. Every other character is encoded as UTF-8 and appended as-is.
+pub fn unescape_bytes(symbol: &str) -> Result<Vec<u8>, UnescapeError> {
+    let chars: Vec<char> = symbol.chars().collect();
+    let mut out: Vec<u8> = Vec::with_capacity(symbol.len());
+    let mut i = 0;
+
+    while i < chars.len() {
+        let c = chars[i];
+        if c != '\\' {
+            let mut buf = [0u8; 4];
+            out.extend_from_slice(c.encode_utf8(&mut buf).as_bytes());
+            i += 1;
+            continue;
+        }
+
+        let start = i;
+        i += 1;
+        let escape = chars.get(i).copied().ok_or(UnescapeError {
+            kind: UnescapeErrorKind::LoneBackslash,
+            loc: SrcLoc::from(start),
+        })?;
+
+        match escape {
+            'n' => {
+                out.push(b'\n');
+                i += 1;
+            }
+            't' => {
+                out.push(b'\t');
+                i += 1;
+            }
+            'r' => {
+                out.push(b'\r');
+                i += 1;
+            }
+            '\\' => {
+                out.push(b'\\');
+                i += 1;
+            }
+            '\'' => {
+                out.push(b'\'');
+                i += 1;
+            }
+            '"' => {
+                out.push(b'"');
+                i += 1;
+            }
+            '0' => {
+                out.push(0);
+                i += 1;
+            }
+            'x' => {
+                let digits: String = chars[i + 1..].iter().take(2).collect();
+                if digits.len() != 2 || !digits.chars().all(|d| d.is_ascii_hexdigit()) {
+                    return Err(UnescapeError {
+                        kind: UnescapeErrorKind::InvalidHexEscape,
+                        loc: SrcLoc::from(start),
+                    });
+                }
+                let byte = u8::from_str_radix(&digits, 16).unwrap();
+                out.push(byte);
+                i += 1 + digits.len();
+            }
+            'u' => {
+                return Err(UnescapeError {
+                    kind: UnescapeErrorKind::UnicodeEscapeInByteLiteral,
+                    loc: SrcLoc::from(start),
+                });
+            }
+            '\n' => {
+                i += 1;
+                while chars.get(i).map(|c| c.is_whitespace()).unwrap_or(false) {
+                    i += 1;
+                }
+            }
+            other => {
+                return Err(UnescapeError {
+                    kind: UnescapeErrorKind::UnknownEscape(other),
+                    loc: SrcLoc::from(start),
+                });
+            }
+        }
+    }
+
+    Ok(out)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn unescape_plain_text_is_unchanged() {
+        assert_eq!(unescape("hello world").unwrap(), "hello world");
+    }
+
+    #[test]
+    fn unescape_standard_escapes() {
+        assert_eq!(unescape(r#"a\nb\tc\\d\'e\"f\0g"#).unwrap(), "a\nb\tc\\d'e\"f\0g");
+    }
+
+    #[test]
+    fn unescape_hex_byte_escape() {
+        assert_eq!(unescape(r"\x41\x42").unwrap(), "AB");
+    }
+
+    #[test]
+    fn unescape_unicode_escape() {
+        assert_eq!(unescape(r"\u{1F600}").unwrap(), "\u{1F600}");
+    }
+
+    #[test]
+    fn unescape_line_continuation_eats_leading_whitespace() {
+        assert_eq!(unescape("a\\\n   b").unwrap(), "ab");
+    }
+
+    #[test]
+    fn unescape_rejects_surrogate_codepoint() {
+        let err = unescape(r"\u{D800}").unwrap_err();
+        assert_eq!(err.kind, UnescapeErrorKind::InvalidCodepoint(0xD800));
+    }
+
+    #[test]
+    fn unescape_rejects_codepoint_above_max() {
+        let err = unescape(r"\u{110000}").unwrap_err();
+        assert_eq!(err.kind, UnescapeErrorKind::InvalidCodepoint(0x110000));
+    }
+
+    #[test]
+    fn unescape_rejects_unknown_escape() {
+        let err = unescape(r"\q").unwrap_err();
+        assert_eq!(err.kind, UnescapeErrorKind::UnknownEscape('q'));
+    }
+
+    #[test]
+    fn unescape_rejects_lone_trailing_backslash() {
+        let err = unescape("abc\\").unwrap_err();
+        assert_eq!(err.kind, UnescapeErrorKind::LoneBackslash);
+    }
+
+    #[test]
+    fn lit_unescape_skips_raw_literals() {
+        let lit = Lit::new(LitKind::Str, r"\n".to_string(), None, true);
+        assert_eq!(lit.unescape().unwrap(), LitValue::Str(r"\n".to_string()));
+    }
+
+    #[test]
+    fn unescape_bytes_hex_escape_yields_raw_byte() {
+        // 0xFF must come through as the single raw byte, not its UTF-8 encoding (which would
+        // be the two bytes [0xC3, 0xBF], i.e. U+00FF re-encoded).
+        assert_eq!(unescape_bytes(r"\xFF").unwrap(), vec![0xFFu8]);
+    }
+
+    #[test]
+    fn unescape_bytes_rejects_unicode_escape() {
+        let err = unescape_bytes(r"\u{41}").unwrap_err();
+        assert_eq!(err.kind, UnescapeErrorKind::UnicodeEscapeInByteLiteral);
+    }
+
+    #[test]
+    fn lit_unescape_byte_str_yields_raw_bytes() {
+        let lit = Lit::new(LitKind::ByteStr, r"a\xFFb".to_string(), None, false);
+        assert_eq!(lit.unescape().unwrap(), LitValue::Bytes(vec![b'a', 0xFF, b'b']));
+    }
+
+    #[test]
+    fn lit_unescape_str_yields_str_value() {
+        let lit = Lit::new(LitKind::Str, r"\x41".to_string(), None, false);
+        assert_eq!(lit.unescape().unwrap(), LitValue::Str("A".to_string()));
+    }
+}
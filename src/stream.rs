@@ -0,0 +1,294 @@
+// Tok Stream Library
+//
+//
+// MIT License
+//
+// Copyright (c) 2021, 2022 Systopia Lab, Computer Science, University of British Columbia
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Token Streams for Parsing
+//!
+//! [TokStream] is the cursor a recursive-descent parser actually drives: a position into a
+//! flat token vector, with [TokKind::keep] consulted on every access so discarded tokens (
+//! comments, whitespace) are invisible to the caller. [TokStream::mark] and [TokStream::reset]
+//! give parsers a way to speculatively try a production and backtrack, and the stream hands
+//! out a stable sentinel token once it runs out of input rather than forcing every call site
+//! to juggle an `Option`.
+
+use std::fmt::Display;
+
+use crate::tok::{Tok, TokKind};
+
+/// An opaque cursor position within a [TokStream], obtained from [TokStream::mark] and
+/// restored with [TokStream::reset].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct Mark(usize);
+
+/// A peekable, filtering stream of [Tok] values for a parser to consume.
+///
+/// Tokens for which [TokKind::keep] is `false` are transparently skipped by [TokStream::bump],
+/// [TokStream::peek], [TokStream::peek_nth], [TokStream::eat] and [TokStream::expect]; use
+/// [TokStream::filtered] to drop them up front instead of skipping them on every access.
+#[derive(Clone, Debug)]
+pub struct TokStream<T>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    tokens: Vec<Tok<T>>,
+    eof: Tok<T>,
+    pos: usize,
+}
+
+impl<T> TokStream<T>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    /// Creates a new [TokStream] over `tokens`, using `eof` as the sentinel returned once the
+    /// cursor passes the end of input.
+    pub fn new(tokens: Vec<Tok<T>>, eof: Tok<T>) -> Self {
+        TokStream { tokens, eof, pos: 0 }
+    }
+
+    /// Creates a new [TokStream] that has permanently dropped every token for which
+    /// [TokKind::keep] returns `false`.
+    pub fn filtered(tokens: Vec<Tok<T>>, eof: Tok<T>) -> Self {
+        let tokens = tokens.into_iter().filter(|tok| tok.keep()).collect();
+        TokStream::new(tokens, eof)
+    }
+
+    /// Advances the cursor to the next index holding a kept token, starting from `pos`.
+    fn skip_discarded(&self, mut pos: usize) -> usize {
+        while pos < self.tokens.len() && !self.tokens[pos].keep() {
+            pos += 1;
+        }
+        pos
+    }
+
+    /// Obtains the `k`-th kept token ahead of the cursor without consuming it. `peek_nth(0)` is
+    /// equivalent to [TokStream::peek].
+    pub fn peek_nth(&self, k: usize) -> &Tok<T> {
+        let mut pos = self.skip_discarded(self.pos);
+        for _ in 0..k {
+            pos = self.skip_discarded(pos + 1);
+        }
+        self.tokens.get(pos).unwrap_or(&self.eof)
+    }
+
+    /// Obtains the next kept token without consuming it, or the EOF sentinel past the end.
+    pub fn peek(&self) -> &Tok<T> {
+        self.peek_nth(0)
+    }
+
+    /// Consumes and returns the next kept token, or the EOF sentinel past the end.
+    ///
+    /// Named `bump` rather than `next` so this isn't mistaken for `Iterator::next`, which it
+    /// deliberately doesn't implement (it returns a sentinel token, not an `Option`).
+    pub fn bump(&mut self) -> Tok<T> {
+        let pos = self.skip_discarded(self.pos);
+        match self.tokens.get(pos) {
+            Some(tok) => {
+                self.pos = pos + 1;
+                tok.clone()
+            }
+            None => {
+                self.pos = pos;
+                self.eof.clone()
+            }
+        }
+    }
+
+    /// If the next kept token's kind equals `kind`, consumes and returns it.
+    pub fn eat(&mut self, kind: &T) -> Option<Tok<T>> {
+        if self.peek().kind() == kind {
+            Some(self.bump())
+        } else {
+            None
+        }
+    }
+
+    /// Consumes the next kept token if its kind equals `kind`, otherwise returns the token
+    /// found instead as an error.
+    pub fn expect(&mut self, kind: &T) -> Result<Tok<T>, Tok<T>> {
+        if self.peek().kind() == kind {
+            Ok(self.bump())
+        } else {
+            Err(self.peek().clone())
+        }
+    }
+
+    /// Returns an opaque marker for the current cursor position, to later [TokStream::reset] to.
+    pub fn mark(&self) -> Mark {
+        Mark(self.pos)
+    }
+
+    /// Rewinds the cursor to a position previously obtained from [TokStream::mark].
+    pub fn reset(&mut self, mark: Mark) {
+        self.pos = mark.0;
+    }
+
+    /// Returns true once the stream has no more kept tokens to yield.
+    pub fn is_eof(&self) -> bool {
+        self.skip_discarded(self.pos) >= self.tokens.len()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::{Display, Formatter, Result as FmtResult};
+
+    use srcspan::SrcSpan;
+
+    #[derive(PartialEq, Clone, Debug)]
+    enum TK {
+        A,
+        B,
+        C,
+        Comment,
+        Eof,
+    }
+
+    impl Display for TK {
+        fn fmt(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "{self:?}")
+        }
+    }
+
+    impl TokKind for TK {
+        fn is_keyword(&self) -> bool {
+            false
+        }
+        fn is_reserved(&self) -> bool {
+            false
+        }
+        fn is_comment(&self) -> bool {
+            matches!(self, TK::Comment)
+        }
+        fn is_literal(&self) -> bool {
+            false
+        }
+        fn is_identifier(&self) -> bool {
+            false
+        }
+        fn keep(&self) -> bool {
+            !matches!(self, TK::Comment)
+        }
+        fn open_delim(&self) -> Option<crate::tree::Delimiter> {
+            None
+        }
+        fn close_delim(&self) -> Option<crate::tree::Delimiter> {
+            None
+        }
+        fn glue(&self, _next: &Self) -> Option<Self> {
+            None
+        }
+        fn as_lit(&self) -> Option<crate::lit::Lit> {
+            None
+        }
+        fn as_doc_comment(&self) -> Option<(crate::comment::CommentKind, crate::comment::AttrStyle, String)> {
+            None
+        }
+        fn dummy() -> Self {
+            TK::Eof
+        }
+    }
+
+    fn tok(kind: TK) -> Tok<TK> {
+        Tok::new(kind, SrcSpan::default())
+    }
+
+    fn eof() -> Tok<TK> {
+        tok(TK::Eof)
+    }
+
+    #[test]
+    fn bump_skips_discarded_tokens_and_returns_eof_past_the_end() {
+        let mut s = TokStream::new(vec![tok(TK::Comment), tok(TK::A), tok(TK::Comment), tok(TK::B)], eof());
+
+        assert_eq!(*s.bump().kind(), TK::A);
+        assert_eq!(*s.bump().kind(), TK::B);
+        assert_eq!(*s.bump().kind(), TK::Eof);
+        // bumping past the end keeps yielding the sentinel, not panicking
+        assert_eq!(*s.bump().kind(), TK::Eof);
+    }
+
+    #[test]
+    fn peek_does_not_consume() {
+        let mut s = TokStream::new(vec![tok(TK::A), tok(TK::B)], eof());
+        assert_eq!(*s.peek().kind(), TK::A);
+        assert_eq!(*s.peek().kind(), TK::A);
+        assert_eq!(*s.bump().kind(), TK::A);
+        assert_eq!(*s.peek().kind(), TK::B);
+    }
+
+    #[test]
+    fn peek_nth_looks_past_discarded_tokens() {
+        let s = TokStream::new(vec![tok(TK::A), tok(TK::Comment), tok(TK::B), tok(TK::C)], eof());
+        assert_eq!(*s.peek_nth(0).kind(), TK::A);
+        assert_eq!(*s.peek_nth(1).kind(), TK::B);
+        assert_eq!(*s.peek_nth(2).kind(), TK::C);
+        assert_eq!(*s.peek_nth(3).kind(), TK::Eof);
+    }
+
+    #[test]
+    fn eat_consumes_only_on_match() {
+        let mut s = TokStream::new(vec![tok(TK::A), tok(TK::B)], eof());
+        assert!(s.eat(&TK::B).is_none());
+        assert!(s.eat(&TK::A).is_some());
+        assert_eq!(*s.peek().kind(), TK::B);
+    }
+
+    #[test]
+    fn expect_returns_the_unexpected_token_as_the_error() {
+        let mut s = TokStream::new(vec![tok(TK::A)], eof());
+        let err = s.expect(&TK::B).unwrap_err();
+        assert_eq!(*err.kind(), TK::A);
+        // a failed expect does not consume
+        assert_eq!(*s.expect(&TK::A).unwrap().kind(), TK::A);
+    }
+
+    #[test]
+    fn mark_and_reset_allow_backtracking() {
+        let mut s = TokStream::new(vec![tok(TK::A), tok(TK::B), tok(TK::C)], eof());
+        let mark = s.mark();
+        assert_eq!(*s.bump().kind(), TK::A);
+        assert_eq!(*s.bump().kind(), TK::B);
+        s.reset(mark);
+        assert_eq!(*s.bump().kind(), TK::A);
+        assert_eq!(*s.bump().kind(), TK::B);
+        assert_eq!(*s.bump().kind(), TK::C);
+    }
+
+    #[test]
+    fn is_eof_accounts_for_trailing_discarded_tokens() {
+        let mut s = TokStream::new(vec![tok(TK::A), tok(TK::Comment)], eof());
+        assert!(!s.is_eof());
+        s.bump();
+        assert!(s.is_eof());
+    }
+
+    #[test]
+    fn filtered_drops_discarded_tokens_up_front() {
+        let mut s = TokStream::filtered(vec![tok(TK::Comment), tok(TK::A), tok(TK::Comment), tok(TK::B)], eof());
+        assert_eq!(*s.bump().kind(), TK::A);
+        assert_eq!(*s.bump().kind(), TK::B);
+        assert_eq!(*s.bump().kind(), TK::Eof);
+    }
+}
@@ -39,6 +39,9 @@ use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
 // used third-party libraries
 use srcspan::{SrcLoc, SrcSpan};
 
+// used crate modules
+use crate::tree::Delimiter;
+
 /// Trait to be implemented by the concrete token kind type.
 pub trait TokKind {
     /// whether the token is a keyword
@@ -58,6 +61,28 @@ pub trait TokKind {
 
     /// whether or not to keep the token when filtering it
     fn keep(&self) -> bool;
+
+    /// if this token opens a delimited group, the delimiter it opens
+    fn open_delim(&self) -> Option<Delimiter>;
+
+    /// if this token closes a delimited group, the delimiter it closes
+    fn close_delim(&self) -> Option<Delimiter>;
+
+    /// if this kind can be glued to `next` to form a single, compound kind, returns it
+    fn glue(&self, next: &Self) -> Option<Self>
+    where
+        Self: Sized;
+
+    /// if this token is a literal, the decomposed literal value it denotes
+    fn as_lit(&self) -> Option<crate::lit::Lit>;
+
+    /// if this token is a documentation comment, its kind, style and stripped body text
+    fn as_doc_comment(&self) -> Option<(crate::comment::CommentKind, crate::comment::AttrStyle, String)>;
+
+    /// a reserved, throwaway kind used to fill the hole left by [Tok::take]
+    fn dummy() -> Self
+    where
+        Self: Sized;
 }
 
 /// Represents a Lexical Token
@@ -119,6 +144,31 @@ where
     pub fn keep(&self) -> bool {
         self.kind.keep()
     }
+
+    /// Attempts to glue this token together with `next` into a single, compound token.
+    ///
+    /// The resulting token's span runs from the start of `self` to the end of `next`.
+    /// Returns `None` if `self.kind()` and `next.kind()` cannot be glued.
+    pub fn glue_with(&self, next: &Tok<T>) -> Option<Tok<T>> {
+        let kind = self.kind.glue(&next.kind)?;
+        Some(Tok::new(kind, self.span.to(&next.span)))
+    }
+
+    /// Creates a placeholder [Tok] with [TokKind::dummy] as its kind and an empty [SrcSpan].
+    ///
+    /// Used by [Tok::take] to fill the hole left behind when a token is moved out by value, and
+    /// more generally to splice recovery tokens into a stream when a real token is missing.
+    pub fn dummy() -> Self {
+        Tok::new(T::dummy(), SrcSpan::default())
+    }
+
+    /// Returns this token by value, replacing it in place with [Tok::dummy].
+    ///
+    /// This lets a parser move a token out of a buffer it only holds `&mut` access to, without
+    /// requiring `T: Default` or cloning `T`.
+    pub fn take(&mut self) -> Tok<T> {
+        std::mem::replace(self, Tok::dummy())
+    }
 }
 
 /// Implementation of the [Display] trait for [Tok]
@@ -130,3 +180,152 @@ where
         Display::fmt(&self.kind, f)
     }
 }
+
+/// Greedily glues adjacent tokens in `tokens` into compound tokens.
+///
+/// Lexers in this design emit atomic, single-character tokens (e.g. two separate `.` tokens
+/// rather than `..`). This pass repeatedly looks at the current token and its successor and,
+/// while [TokKind::glue] yields a combined kind, replaces the pair with a single glued token
+/// before attempting to glue the result with whatever follows. Tokens that cannot be glued
+/// with their successor are passed through unchanged.
+pub fn glue_stream<T>(tokens: Vec<Tok<T>>) -> Vec<Tok<T>>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    let mut out: Vec<Tok<T>> = Vec::with_capacity(tokens.len());
+
+    for tok in tokens {
+        match out.last().and_then(|prev| prev.glue_with(&tok)) {
+            Some(glued) => {
+                out.pop();
+                out.push(glued);
+            }
+            None => out.push(tok),
+        }
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[derive(PartialEq, Clone, Debug)]
+    enum TK {
+        Dot,
+        DotDot,
+        Eq,
+        EqEq,
+        Ident,
+        Dummy,
+    }
+
+    impl Display for TK {
+        fn fmt(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "{self:?}")
+        }
+    }
+
+    impl TokKind for TK {
+        fn is_keyword(&self) -> bool {
+            false
+        }
+        fn is_reserved(&self) -> bool {
+            false
+        }
+        fn is_comment(&self) -> bool {
+            false
+        }
+        fn is_literal(&self) -> bool {
+            false
+        }
+        fn is_identifier(&self) -> bool {
+            matches!(self, TK::Ident)
+        }
+        fn keep(&self) -> bool {
+            true
+        }
+        fn open_delim(&self) -> Option<Delimiter> {
+            None
+        }
+        fn close_delim(&self) -> Option<Delimiter> {
+            None
+        }
+        fn glue(&self, next: &Self) -> Option<Self> {
+            match (self, next) {
+                (TK::Dot, TK::Dot) => Some(TK::DotDot),
+                (TK::Eq, TK::Eq) => Some(TK::EqEq),
+                _ => None,
+            }
+        }
+        fn as_lit(&self) -> Option<crate::lit::Lit> {
+            None
+        }
+        fn as_doc_comment(&self) -> Option<(crate::comment::CommentKind, crate::comment::AttrStyle, String)> {
+            None
+        }
+        fn dummy() -> Self {
+            TK::Dummy
+        }
+    }
+
+    fn spans(lo: usize, hi: usize) -> SrcSpan {
+        SrcSpan::new(SrcLoc::from(lo), SrcLoc::from(hi))
+    }
+
+    #[test]
+    fn glue_with_merges_kind_and_span() {
+        let first = Tok::new(TK::Dot, spans(0, 1));
+        let second = Tok::new(TK::Dot, spans(1, 2));
+
+        let glued = first.glue_with(&second).unwrap();
+
+        assert_eq!(*glued.kind(), TK::DotDot);
+        assert_eq!(*glued.span(), first.span().to(second.span()));
+    }
+
+    #[test]
+    fn glue_with_returns_none_when_kinds_cannot_glue() {
+        let dot = Tok::new(TK::Dot, spans(0, 1));
+        let ident = Tok::new(TK::Ident, spans(1, 2));
+        assert!(dot.glue_with(&ident).is_none());
+    }
+
+    #[test]
+    fn glue_stream_folds_an_adjacent_gluable_pair() {
+        let tokens = vec![Tok::new(TK::Dot, spans(0, 1)), Tok::new(TK::Dot, spans(1, 2)), Tok::new(TK::Ident, spans(2, 3))];
+
+        let glued = glue_stream(tokens);
+
+        assert_eq!(glued.len(), 2);
+        assert_eq!(*glued[0].kind(), TK::DotDot);
+        assert_eq!(*glued[1].kind(), TK::Ident);
+    }
+
+    #[test]
+    fn glue_stream_leaves_non_gluable_tokens_untouched() {
+        let tokens = vec![Tok::new(TK::Ident, spans(0, 1)), Tok::new(TK::Eq, spans(1, 2))];
+        let glued = glue_stream(tokens.clone());
+        assert_eq!(glued, tokens);
+    }
+
+    #[test]
+    fn dummy_has_the_kinds_dummy_and_an_empty_span() {
+        let dummy = Tok::<TK>::dummy();
+        assert_eq!(*dummy.kind(), TK::Dummy);
+        assert_eq!(*dummy.span(), SrcSpan::default());
+    }
+
+    #[test]
+    fn take_returns_the_original_token_and_leaves_a_dummy_behind() {
+        let mut tok = Tok::new(TK::Ident, spans(0, 1));
+
+        let taken = tok.take();
+
+        assert_eq!(*taken.kind(), TK::Ident);
+        assert_eq!(*taken.span(), spans(0, 1));
+        assert_eq!(*tok.kind(), TK::Dummy);
+        assert_eq!(*tok.span(), SrcSpan::default());
+    }
+}
@@ -0,0 +1,398 @@
+// Tok Stream Library
+//
+//
+// MIT License
+//
+// Copyright (c) 2021, 2022 Systopia Lab, Computer Science, University of British Columbia
+//
+// Permission is hereby granted, free of charge, to any person obtaining a copy
+// of this software and associated documentation files (the "Software"), to deal
+// in the Software without restriction, including without limitation the rights
+// to use, copy, modify, merge, publish, distribute, sublicense, and/or sell
+// copies of the Software, and to permit persons to whom the Software is
+// furnished to do so, subject to the following conditions:
+//
+// The above copyright notice and this permission notice shall be included in all
+// copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND, EXPRESS OR
+// IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF MERCHANTABILITY,
+// FITNESS FOR A PARTICULAR PURPOSE AND NONINFRINGEMENT. IN NO EVENT SHALL THE
+// AUTHORS OR COPYRIGHT HOLDERS BE LIABLE FOR ANY CLAIM, DAMAGES OR OTHER
+// LIABILITY, WHETHER IN AN ACTION OF CONTRACT, TORT OR OTHERWISE, ARISING FROM,
+// OUT OF OR IN CONNECTION WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE
+// SOFTWARE.
+
+//! # Token Trees
+//!
+//! Grouping turns the flat token vector produced by a lexer into a tree shaped like the
+//! source: [TokenTree::Leaf] for ordinary tokens and [TokenTree::Group] for anything enclosed
+//! by a matched pair of delimiters. A parser can then recurse into a [Group]'s inner
+//! [TokenStream] instead of having to track delimiter depth itself.
+
+use std::fmt::{Debug, Display, Formatter, Result as FmtResult};
+
+use srcspan::SrcSpan;
+
+use crate::tok::{Tok, TokKind};
+
+/// The kind of delimiter that brackets a [TokenTree::Group].
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub enum Delimiter {
+    /// `( ... )`
+    Paren,
+    /// `[ ... ]`
+    Bracket,
+    /// `{ ... }`
+    Brace,
+    /// an empty, invisible delimiter group
+    None,
+}
+
+/// The combined span of a delimited group's opening and closing tokens.
+#[derive(PartialEq, Eq, Clone, Copy, Debug)]
+pub struct DelimSpan {
+    /// the span of the opening delimiter
+    pub open: SrcSpan,
+    /// the span of the closing delimiter
+    pub close: SrcSpan,
+}
+
+impl DelimSpan {
+    /// Creates a new [DelimSpan] from the open and close delimiter spans
+    pub fn new(open: SrcSpan, close: SrcSpan) -> Self {
+        DelimSpan { open, close }
+    }
+
+    /// Obtains the span covering the entire group, from the open to the close delimiter
+    pub fn entire(&self) -> SrcSpan {
+        self.open.to(&self.close)
+    }
+}
+
+/// A single node of a [TokenStream]: either a plain token, or a delimited
+/// group of further token trees.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum TokenTree<T>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    /// a single, non-delimiter token
+    Leaf(Tok<T>),
+    /// a delimiter-balanced group of token trees
+    Group(Group<T>),
+}
+
+/// A delimiter-balanced group of token trees, e.g. the contents of `( ... )`.
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub struct Group<T>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    /// the kind of delimiter bracketing this group
+    pub delim: Delimiter,
+    /// the opening delimiter token itself
+    pub open: Tok<T>,
+    /// the closing delimiter token itself
+    pub close: Tok<T>,
+    /// the token trees enclosed by the delimiters
+    pub stream: TokenStream<T>,
+}
+
+impl<T> Group<T>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    /// Obtains the spans of this group's opening and closing delimiter tokens
+    pub fn span(&self) -> DelimSpan {
+        DelimSpan::new(*self.open.span(), *self.close.span())
+    }
+}
+
+/// A tree of [TokenTree] nodes produced by grouping a flat token vector.
+#[derive(PartialEq, Eq, Clone, Debug, Default)]
+pub struct TokenStream<T>(Vec<TokenTree<T>>)
+where
+    T: TokKind + Display + PartialEq + Clone;
+
+impl<T> TokenStream<T>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    /// Creates a new [TokenStream] from already-grouped token trees
+    pub fn new(trees: Vec<TokenTree<T>>) -> Self {
+        TokenStream(trees)
+    }
+
+    /// Obtains the top-level token trees of this stream
+    pub fn trees(&self) -> &[TokenTree<T>] {
+        &self.0
+    }
+
+    /// Returns an iterator walking the top-level token trees of this stream
+    pub fn iter(&self) -> std::slice::Iter<'_, TokenTree<T>> {
+        self.0.iter()
+    }
+
+    /// Flattens this tree back into the original, linear token order, including the
+    /// delimiter tokens that bracket each [Group]
+    pub fn flatten(&self) -> Vec<Tok<T>> {
+        let mut out = Vec::new();
+        flatten_into(&self.0, &mut out);
+        out
+    }
+}
+
+fn flatten_into<T>(trees: &[TokenTree<T>], out: &mut Vec<Tok<T>>)
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    for tree in trees {
+        match tree {
+            TokenTree::Leaf(tok) => out.push(tok.clone()),
+            TokenTree::Group(group) => {
+                out.push(group.open.clone());
+                flatten_into(group.stream.trees(), out);
+                out.push(group.close.clone());
+            }
+        }
+    }
+}
+
+/// An error produced while grouping a flat token vector into a [TokenStream].
+#[derive(PartialEq, Eq, Clone, Debug)]
+pub enum GroupingError {
+    /// a closing delimiter did not match the delimiter of the innermost open group
+    Mismatch {
+        /// the span of the opening delimiter of the group that was left open
+        open: SrcSpan,
+        /// the span of the closing delimiter that did not match
+        close: SrcSpan,
+    },
+    /// a closing delimiter was seen with no matching open group
+    Unmatched {
+        /// the span of the unexpected closing delimiter
+        close: SrcSpan,
+    },
+    /// input ended with one or more delimited groups still open
+    Unclosed {
+        /// the spans of every open delimiter whose group was never closed, outermost first
+        opens: Vec<SrcSpan>,
+    },
+}
+
+impl Display for GroupingError {
+    fn fmt(&self, f: &mut Formatter) -> FmtResult {
+        match self {
+            GroupingError::Mismatch { open, close } => {
+                write!(f, "mismatched closing delimiter at {close:?}, group opened at {open:?}")
+            }
+            GroupingError::Unmatched { close } => {
+                write!(f, "unmatched closing delimiter at {close:?}")
+            }
+            GroupingError::Unclosed { opens } => {
+                write!(f, "unclosed delimiter group(s) opened at {opens:?}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for GroupingError {}
+
+/// A single frame of the grouping stack: the delimiter and open token of a
+/// group currently being accumulated, plus its children so far.
+struct Frame<T>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    delim: Delimiter,
+    open: Tok<T>,
+    children: Vec<TokenTree<T>>,
+}
+
+/// Groups a flat vector of tokens into a [TokenStream] of delimiter-balanced
+/// [TokenTree]s.
+///
+/// This is a single left-to-right pass over `tokens` with an explicit stack:
+/// on an opening delimiter a new [Frame] is pushed recording the open token's
+/// span; on a closing delimiter the frame is popped and its delimiter
+/// compared against the closing token, producing a [GroupingError::Mismatch]
+/// on a mismatch; every other token is pushed as a [TokenTree::Leaf] onto the
+/// current frame. Any frames still open once `tokens` is exhausted are all reported together
+/// via [GroupingError::Unclosed], outermost first.
+pub fn group_tokens<T>(tokens: Vec<Tok<T>>) -> Result<TokenStream<T>, GroupingError>
+where
+    T: TokKind + Display + PartialEq + Clone,
+{
+    let mut stack: Vec<Frame<T>> = Vec::new();
+    let mut top: Vec<TokenTree<T>> = Vec::new();
+
+    for tok in tokens {
+        if let Some(delim) = tok.kind().open_delim() {
+            stack.push(Frame {
+                delim,
+                open: tok,
+                children: Vec::new(),
+            });
+            continue;
+        }
+
+        if let Some(delim) = tok.kind().close_delim() {
+            let frame = stack.pop().ok_or(GroupingError::Unmatched { close: *tok.span() })?;
+            if frame.delim != delim {
+                return Err(GroupingError::Mismatch {
+                    open: *frame.open.span(),
+                    close: *tok.span(),
+                });
+            }
+            let group = Group {
+                delim: frame.delim,
+                open: frame.open,
+                close: tok,
+                stream: TokenStream::new(frame.children),
+            };
+            let dest = stack.last_mut().map(|f| &mut f.children).unwrap_or(&mut top);
+            dest.push(TokenTree::Group(group));
+            continue;
+        }
+
+        let dest = stack.last_mut().map(|f| &mut f.children).unwrap_or(&mut top);
+        dest.push(TokenTree::Leaf(tok));
+    }
+
+    if !stack.is_empty() {
+        let opens = stack.iter().map(|frame| *frame.open.span()).collect();
+        return Err(GroupingError::Unclosed { opens });
+    }
+
+    Ok(TokenStream::new(top))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::fmt::{Display, Formatter, Result as FmtResult};
+
+    #[derive(PartialEq, Clone, Debug)]
+    enum TK {
+        LParen,
+        RParen,
+        LBracket,
+        RBracket,
+        Ident,
+    }
+
+    impl Display for TK {
+        fn fmt(&self, f: &mut Formatter) -> FmtResult {
+            write!(f, "{self:?}")
+        }
+    }
+
+    impl TokKind for TK {
+        fn is_keyword(&self) -> bool {
+            false
+        }
+        fn is_reserved(&self) -> bool {
+            false
+        }
+        fn is_comment(&self) -> bool {
+            false
+        }
+        fn is_literal(&self) -> bool {
+            false
+        }
+        fn is_identifier(&self) -> bool {
+            matches!(self, TK::Ident)
+        }
+        fn keep(&self) -> bool {
+            true
+        }
+        fn open_delim(&self) -> Option<Delimiter> {
+            match self {
+                TK::LParen => Some(Delimiter::Paren),
+                TK::LBracket => Some(Delimiter::Bracket),
+                _ => None,
+            }
+        }
+        fn close_delim(&self) -> Option<Delimiter> {
+            match self {
+                TK::RParen => Some(Delimiter::Paren),
+                TK::RBracket => Some(Delimiter::Bracket),
+                _ => None,
+            }
+        }
+        fn glue(&self, _next: &Self) -> Option<Self> {
+            None
+        }
+        fn as_lit(&self) -> Option<crate::lit::Lit> {
+            None
+        }
+        fn as_doc_comment(&self) -> Option<(crate::comment::CommentKind, crate::comment::AttrStyle, String)> {
+            None
+        }
+        fn dummy() -> Self {
+            TK::Ident
+        }
+    }
+
+    fn tok(kind: TK) -> Tok<TK> {
+        Tok::new(kind, SrcSpan::default())
+    }
+
+    #[test]
+    fn groups_well_formed_nested_input_and_flattens_back() {
+        // ( ident [ ident ] )
+        let tokens = vec![
+            tok(TK::LParen),
+            tok(TK::Ident),
+            tok(TK::LBracket),
+            tok(TK::Ident),
+            tok(TK::RBracket),
+            tok(TK::RParen),
+        ];
+
+        let stream = group_tokens(tokens.clone()).unwrap();
+
+        // one top-level group, containing an ident leaf and a nested group
+        assert_eq!(stream.trees().len(), 1);
+        let TokenTree::Group(outer) = &stream.trees()[0] else {
+            panic!("expected a group");
+        };
+        assert_eq!(outer.delim, Delimiter::Paren);
+        assert_eq!(outer.stream.trees().len(), 2);
+
+        assert_eq!(stream.flatten(), tokens);
+    }
+
+    #[test]
+    fn mismatched_closing_delimiter_is_reported() {
+        let tokens = vec![tok(TK::LParen), tok(TK::RBracket)];
+        let err = group_tokens(tokens).unwrap_err();
+        assert!(matches!(err, GroupingError::Mismatch { .. }));
+    }
+
+    #[test]
+    fn unmatched_closing_delimiter_is_reported() {
+        let tokens = vec![tok(TK::Ident), tok(TK::RParen)];
+        let err = group_tokens(tokens).unwrap_err();
+        assert!(matches!(err, GroupingError::Unmatched { .. }));
+    }
+
+    #[test]
+    fn unclosed_group_at_eof_is_reported() {
+        let tokens = vec![tok(TK::LParen), tok(TK::Ident)];
+        let err = group_tokens(tokens).unwrap_err();
+        assert!(matches!(err, GroupingError::Unclosed { .. }));
+    }
+
+    #[test]
+    fn unclosed_reports_every_still_open_frame() {
+        // ( [ ident   -- both the paren and the bracket are left open
+        let tokens = vec![tok(TK::LParen), tok(TK::LBracket), tok(TK::Ident)];
+        let err = group_tokens(tokens).unwrap_err();
+        match err {
+            GroupingError::Unclosed { opens } => assert_eq!(opens.len(), 2),
+            other => panic!("expected Unclosed, got {other:?}"),
+        }
+    }
+}